@@ -0,0 +1,352 @@
+use std::{
+    io::Write,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use solana_client::{
+    nonblocking::pubsub_client::PubsubClient,
+    pubsub_client::PubsubClientError,
+    rpc_config::{RpcBlockSubscribeConfig, RpcBlockSubscribeFilter},
+};
+use solana_transaction_status_client_types::{TransactionDetails, UiTransactionEncoding};
+use thiserror::Error;
+use tokio::time::timeout;
+use tracing::{info, warn};
+use url::Url;
+
+use crate::fetcher::{BenchSlotSample, IngestionMode, TransactionFetcher};
+
+#[derive(Debug, Error)]
+pub enum BenchError {
+    #[error(transparent)]
+    PubsubClient(#[from] PubsubClientError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// How a benchmark run selects the slots it drives through the pipeline.
+pub enum BenchWindow {
+    /// Walk a fixed, already-known slot range.
+    Range { from_slot: u64, to_slot: u64 },
+    /// Subscribe over the websocket and drive whatever arrives for
+    /// `duration`, via the subscription `run_live` picks for the fetcher's
+    /// ingestion mode.
+    Live { duration: Duration },
+}
+
+/// Mean and tail latency over a set of samples, in milliseconds.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LatencyStats {
+    pub mean_millis: f64,
+    pub p50_millis: f64,
+    pub p95_millis: f64,
+    pub p99_millis: f64,
+}
+
+impl LatencyStats {
+    fn from_millis(mut samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        samples.sort_by(|a, b| a.partial_cmp(b).expect("latency samples are never NaN"));
+        let mean_millis = samples.iter().sum::<f64>() / samples.len() as f64;
+        Self {
+            mean_millis,
+            p50_millis: percentile(&samples, 0.50),
+            p95_millis: percentile(&samples, 0.95),
+            p99_millis: percentile(&samples, 0.99),
+        }
+    }
+}
+
+fn percentile(sorted_samples: &[f64], p: f64) -> f64 {
+    let index = (((sorted_samples.len() - 1) as f64) * p).round() as usize;
+    sorted_samples[index]
+}
+
+/// Aggregated results of one benchmark run.
+#[derive(Debug, Clone, Default)]
+pub struct BenchReport {
+    pub duration_secs: f64,
+    pub slots: u64,
+    pub transactions: u64,
+    pub slots_per_sec: f64,
+    pub transactions_per_sec: f64,
+    pub block_fetch_latency: LatencyStats,
+    pub transaction_fetch_latency: LatencyStats,
+    /// Lag between a slot's websocket notification and its transactions
+    /// landing in the store. Only populated by `BenchWindow::Live`, since a
+    /// fixed slot range has no real-time arrival event to measure from.
+    pub end_to_end_lag: LatencyStats,
+}
+
+impl BenchReport {
+    /// One human-readable line summarizing the run, for terminal output.
+    pub fn summary_line(&self) -> String {
+        format!(
+            "{:.1} slots/s, {:.1} tx/s over {:.1}s ({} slots, {} tx) — \
+             block fetch p99 {:.1}ms, tx fetch p99 {:.1}ms, e2e lag p99 {:.1}ms",
+            self.slots_per_sec,
+            self.transactions_per_sec,
+            self.duration_secs,
+            self.slots,
+            self.transactions,
+            self.block_fetch_latency.p99_millis,
+            self.transaction_fetch_latency.p99_millis,
+            self.end_to_end_lag.p99_millis,
+        )
+    }
+
+    /// Appends one CSV row to `path`, writing the header first if the file
+    /// doesn't already exist, so repeated runs build up a comparable series.
+    pub fn append_csv_row(&self, path: &Path) -> std::io::Result<()> {
+        let write_header = !path.exists();
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        if write_header {
+            writeln!(
+                file,
+                "duration_secs,slots,transactions,slots_per_sec,transactions_per_sec,\
+                 block_fetch_mean_ms,block_fetch_p50_ms,block_fetch_p95_ms,block_fetch_p99_ms,\
+                 tx_fetch_mean_ms,tx_fetch_p50_ms,tx_fetch_p95_ms,tx_fetch_p99_ms,\
+                 e2e_lag_mean_ms,e2e_lag_p50_ms,e2e_lag_p95_ms,e2e_lag_p99_ms"
+            )?;
+        }
+
+        writeln!(
+            file,
+            "{:.3},{},{},{:.3},{:.3},\
+             {:.3},{:.3},{:.3},{:.3},\
+             {:.3},{:.3},{:.3},{:.3},\
+             {:.3},{:.3},{:.3},{:.3}",
+            self.duration_secs,
+            self.slots,
+            self.transactions,
+            self.slots_per_sec,
+            self.transactions_per_sec,
+            self.block_fetch_latency.mean_millis,
+            self.block_fetch_latency.p50_millis,
+            self.block_fetch_latency.p95_millis,
+            self.block_fetch_latency.p99_millis,
+            self.transaction_fetch_latency.mean_millis,
+            self.transaction_fetch_latency.p50_millis,
+            self.transaction_fetch_latency.p95_millis,
+            self.transaction_fetch_latency.p99_millis,
+            self.end_to_end_lag.mean_millis,
+            self.end_to_end_lag.p50_millis,
+            self.end_to_end_lag.p95_millis,
+            self.end_to_end_lag.p99_millis,
+        )
+    }
+}
+
+/// Accumulates samples across a run before collapsing them into a `BenchReport`.
+#[derive(Default)]
+struct Accumulator {
+    slots: u64,
+    transactions: u64,
+    block_latencies_ms: Vec<f64>,
+    transaction_latencies_ms: Vec<f64>,
+    end_to_end_lags_ms: Vec<f64>,
+}
+
+impl Accumulator {
+    fn record(&mut self, sample: BenchSlotSample, end_to_end_lag: Option<Duration>) {
+        self.slots += 1;
+        self.transactions += sample.transactions_stored as u64;
+        self.block_latencies_ms
+            .push(sample.block_latency.as_secs_f64() * 1000.0);
+        self.transaction_latencies_ms.extend(
+            sample
+                .transaction_latencies
+                .iter()
+                .map(|d| d.as_secs_f64() * 1000.0),
+        );
+        if let Some(lag) = end_to_end_lag {
+            self.end_to_end_lags_ms.push(lag.as_secs_f64() * 1000.0);
+        }
+    }
+
+    fn into_report(self, measured_elapsed: Duration) -> BenchReport {
+        let duration_secs = measured_elapsed.as_secs_f64();
+        let divisor = duration_secs.max(f64::EPSILON);
+        BenchReport {
+            duration_secs,
+            slots: self.slots,
+            transactions: self.transactions,
+            slots_per_sec: self.slots as f64 / divisor,
+            transactions_per_sec: self.transactions as f64 / divisor,
+            block_fetch_latency: LatencyStats::from_millis(self.block_latencies_ms),
+            transaction_fetch_latency: LatencyStats::from_millis(self.transaction_latencies_ms),
+            end_to_end_lag: LatencyStats::from_millis(self.end_to_end_lags_ms),
+        }
+    }
+}
+
+/// Drives `window` through `fetcher`, discarding samples seen during
+/// `warmup` so cold-cache and connection-setup effects don't skew the report.
+pub async fn run(
+    fetcher: &TransactionFetcher,
+    ws_url: &Url,
+    window: BenchWindow,
+    warmup: Duration,
+) -> Result<BenchReport, BenchError> {
+    match window {
+        BenchWindow::Range { from_slot, to_slot } => {
+            run_range(fetcher, from_slot, to_slot, warmup).await
+        }
+        BenchWindow::Live { duration } => run_live(fetcher, ws_url, duration, warmup).await,
+    }
+}
+
+async fn run_range(
+    fetcher: &TransactionFetcher,
+    from_slot: u64,
+    to_slot: u64,
+    warmup: Duration,
+) -> Result<BenchReport, BenchError> {
+    let warmup_until = Instant::now() + warmup;
+    let mut accumulator = Accumulator::default();
+    let mut measured_start = None;
+
+    for slot in from_slot..=to_slot {
+        let sample = fetcher.bench_fetch_slot(slot).await;
+
+        let now = Instant::now();
+        if now < warmup_until {
+            info!("Discarding slot {} sample during warmup", slot);
+            continue;
+        }
+        measured_start.get_or_insert(now);
+        accumulator.record(sample, None);
+    }
+
+    let elapsed = measured_start.map_or(Duration::ZERO, |start| start.elapsed());
+    Ok(accumulator.into_report(elapsed))
+}
+
+/// Drives a live window through a subscription matching `fetcher`'s
+/// `ingestion_mode`: root_subscribe for the legacy mode, block_subscribe
+/// otherwise. Dual-track is driven as a single block subscription at the
+/// fetcher's commitment; its separate confirmed and finalized legs are not
+/// reproduced.
+async fn run_live(
+    fetcher: &TransactionFetcher,
+    ws_url: &Url,
+    duration: Duration,
+    warmup: Duration,
+) -> Result<BenchReport, BenchError> {
+    match fetcher.ingestion_mode() {
+        IngestionMode::BlockSubscribe | IngestionMode::DualTrack => {
+            run_live_block_subscribe(fetcher, ws_url, duration, warmup).await
+        }
+        IngestionMode::RootSubscribe => run_live_root_subscribe(fetcher, ws_url, duration, warmup).await,
+    }
+}
+
+/// Legacy live-window path: subscribe to new roots, then fetch each slot
+/// (and every transaction in it) over RPC, mirroring `run_root_subscribe`.
+async fn run_live_root_subscribe(
+    fetcher: &TransactionFetcher,
+    ws_url: &Url,
+    duration: Duration,
+    warmup: Duration,
+) -> Result<BenchReport, BenchError> {
+    let ws = PubsubClient::new(ws_url.as_str()).await?;
+    let (mut stream, unsubscribe) = ws.root_subscribe().await?;
+
+    let warmup_until = Instant::now() + warmup;
+    let run_until = warmup_until + duration;
+    let mut accumulator = Accumulator::default();
+    let mut measured_start = None;
+
+    while Instant::now() < run_until {
+        let remaining = run_until.saturating_duration_since(Instant::now());
+        let Ok(Some(slot)) = timeout(remaining, stream.next()).await else {
+            break;
+        };
+
+        let arrived_at = Instant::now();
+        let sample = fetcher.bench_fetch_slot(slot).await;
+        let lag = arrived_at.elapsed();
+
+        let now = Instant::now();
+        if now < warmup_until {
+            info!("Discarding slot {} sample during warmup", slot);
+            continue;
+        }
+        measured_start.get_or_insert(now);
+        accumulator.record(sample, Some(lag));
+    }
+
+    unsubscribe().await;
+
+    let elapsed = measured_start.map_or(Duration::ZERO, |start| start.elapsed());
+    Ok(accumulator.into_report(elapsed))
+}
+
+/// Live-window path for the blockSubscribe rewrite: subscribe to full blocks
+/// directly, mirroring `run_block_subscribe`/`store_block`, so this is the
+/// only path that can actually measure the change it was built to benchmark.
+async fn run_live_block_subscribe(
+    fetcher: &TransactionFetcher,
+    ws_url: &Url,
+    duration: Duration,
+    warmup: Duration,
+) -> Result<BenchReport, BenchError> {
+    let ws = PubsubClient::new(ws_url.as_str()).await?;
+    let (mut stream, unsubscribe) = ws
+        .block_subscribe(
+            RpcBlockSubscribeFilter::All,
+            Some(RpcBlockSubscribeConfig {
+                commitment: Some(fetcher.commitment().to_commitment_config()),
+                encoding: Some(UiTransactionEncoding::JsonParsed),
+                transaction_details: Some(TransactionDetails::Full),
+                show_rewards: Some(false),
+                max_supported_transaction_version: Some(0),
+            }),
+        )
+        .await?;
+
+    let warmup_until = Instant::now() + warmup;
+    let run_until = warmup_until + duration;
+    let mut accumulator = Accumulator::default();
+    let mut measured_start = None;
+
+    while Instant::now() < run_until {
+        let remaining = run_until.saturating_duration_since(Instant::now());
+        let Ok(Some(update)) = timeout(remaining, stream.next()).await else {
+            break;
+        };
+
+        let slot = update.value.slot;
+        let arrived_at = Instant::now();
+
+        let Some(block) = update.value.block else {
+            warn!("block_subscribe notification for slot {} carried no block, skipping", slot);
+            continue;
+        };
+        let sample = fetcher.bench_store_block(slot, block).await;
+        let lag = arrived_at.elapsed();
+
+        let now = Instant::now();
+        if now < warmup_until {
+            info!("Discarding slot {} sample during warmup", slot);
+            continue;
+        }
+        measured_start.get_or_insert(now);
+        accumulator.record(sample, Some(lag));
+    }
+
+    unsubscribe().await;
+
+    let elapsed = measured_start.map_or(Duration::ZERO, |start| start.elapsed());
+    Ok(accumulator.into_report(elapsed))
+}
@@ -0,0 +1,136 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::Parser;
+use solana_data_aggregator::{
+    bench::{self, BenchWindow},
+    fetcher::{Commitment, IngestionMode, TransactionFetcher, TransactionFetcherError},
+    metrics::Metrics,
+    store::{DbArgs, DbConnectError},
+};
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tracing::{info, Level};
+use url::Url;
+
+/// Drives `TransactionFetcher` against a slot range or a live window and
+/// reports throughput and latency distributions, to measure the impact of
+/// changes like the blockSubscribe rewrite. `--max-concurrent-fetches` has no
+/// effect on a run: the benchmark paths fetch transactions one at a time and
+/// never acquire the fetcher's concurrency permits.
+#[derive(Debug, Parser)]
+struct Args {
+    #[arg(long, short = 'r', env, value_delimiter = ',')]
+    rpc_urls: Vec<Url>,
+
+    #[arg(long, short, env)]
+    ws_url: Url,
+
+    #[command(flatten)]
+    db: DbArgs,
+
+    /// Ingestion mode to benchmark with `--live`; `run_live` drives the
+    /// matching subscription, e.g. the blockSubscribe rewrite. A slot range
+    /// is always fetched over RPC, whatever the mode.
+    #[arg(long, env, value_enum, default_value_t = IngestionMode::BlockSubscribe)]
+    ingestion_mode: IngestionMode,
+
+    #[arg(long, env, value_enum, default_value_t = Commitment::Finalized)]
+    commitment: Commitment,
+
+    #[arg(long, env, default_value_t = 5)]
+    max_concurrent_fetches: usize,
+
+    #[arg(long, env, default_value_t = 1000)]
+    tx_limit: usize,
+
+    /// First slot to fetch. Required, with `--to-slot`, unless `--live` is
+    /// set; ignored with `--live`.
+    #[arg(long)]
+    from_slot: Option<u64>,
+
+    /// Last slot to fetch (inclusive).
+    #[arg(long)]
+    to_slot: Option<u64>,
+
+    /// Benchmark a live window, via the subscription matching
+    /// `--ingestion-mode`, instead of a fixed slot range.
+    #[arg(long, default_value_t = false)]
+    live: bool,
+
+    /// How long to drive a live window for, in seconds. Ignored for a slot range.
+    #[arg(long, default_value_t = 60)]
+    window_secs: u64,
+
+    /// How long to run before recording samples, in seconds.
+    #[arg(long, default_value_t = 5)]
+    warmup_secs: u64,
+
+    /// CSV file to append the summary row to.
+    #[arg(long)]
+    csv_out: PathBuf,
+}
+
+#[derive(Debug, Error)]
+enum BenchCliError {
+    #[error(transparent)]
+    Db(#[from] DbConnectError),
+
+    #[error(transparent)]
+    TransactionFetcher(#[from] TransactionFetcherError),
+
+    #[error(transparent)]
+    Bench(#[from] bench::BenchError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("--from-slot and --to-slot are required unless --live is set")]
+    MissingRange,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), BenchCliError> {
+    tracing_subscriber::fmt().with_max_level(Level::INFO).init();
+
+    let args = Args::parse();
+
+    let window = if args.live {
+        BenchWindow::Live {
+            duration: Duration::from_secs(args.window_secs),
+        }
+    } else {
+        let from_slot = args.from_slot.ok_or(BenchCliError::MissingRange)?;
+        let to_slot = args.to_slot.ok_or(BenchCliError::MissingRange)?;
+        BenchWindow::Range { from_slot, to_slot }
+    };
+
+    let store = args.db.connect().await?;
+    let metrics = Metrics::new();
+    // The fetcher's own shutdown channel is never used: the bench harness
+    // drives `bench_fetch_slot`/`bench_store_block` directly instead of
+    // calling `run`.
+    let (shutdown_tx, _) = broadcast::channel::<()>(1);
+
+    let fetcher = TransactionFetcher::new(
+        args.rpc_urls,
+        args.ws_url.clone(),
+        store,
+        0,
+        args.tx_limit,
+        args.ingestion_mode,
+        args.commitment,
+        args.max_concurrent_fetches,
+        metrics,
+        shutdown_tx.subscribe(),
+    )
+    .await?;
+
+    info!("Starting benchmark run...");
+    let report = bench::run(&fetcher, &args.ws_url, window, Duration::from_secs(args.warmup_secs)).await?;
+
+    info!("{}", report.summary_line());
+    report.append_csv_row(&args.csv_out)?;
+    info!("Wrote summary row to {}", args.csv_out.display());
+
+    Ok(())
+}
@@ -1,22 +1,79 @@
-use std::str::FromStr;
+use std::{collections::BTreeSet, str::FromStr, sync::Arc};
 
+use clap::ValueEnum;
 use futures::{future::join_all, StreamExt};
-use serde::{Deserialize, Serialize};
 use solana_client::{
-    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    nonblocking::pubsub_client::PubsubClient,
     pubsub_client::PubsubClientError,
-    rpc_config::{RpcBlockConfig, RpcTransactionConfig},
+    rpc_config::{
+        RpcBlockConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter, RpcTransactionConfig,
+    },
 };
 use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
 use solana_transaction_status_client_types::{
-    EncodedConfirmedTransactionWithStatusMeta, TransactionDetails, UiTransactionEncoding,
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+    EncodedTransactionWithStatusMeta, TransactionDetails, UiConfirmedBlock, UiTransactionEncoding,
 };
-use surrealdb::{Connection, RecordId, Surreal};
 use thiserror::Error;
-use tokio::{select, sync::broadcast};
+use tokio::{
+    select,
+    sync::{broadcast, Semaphore},
+};
 use tracing::{error, info, warn};
 use url::Url;
 
+use crate::{
+    metrics::{ErrorKind, Metrics},
+    retry::RetryQueue,
+    rpc_pool::RpcPool,
+    store::{ConfirmationStatus, StoreError, Transaction, TransactionStore},
+};
+
+/// Selects how `TransactionFetcher::run` ingests new transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IngestionMode {
+    /// Subscribe to full blocks via `block_subscribe` and store every
+    /// transaction straight from the notification, without any per-signature
+    /// RPC round-trip.
+    BlockSubscribe,
+    /// Subscribe to new roots and fetch each slot (and each transaction in
+    /// it) over RPC, as this fetcher originally did.
+    RootSubscribe,
+    /// Subscribe to blocks at both `confirmed` and `finalized` commitment,
+    /// storing each confirmed block immediately and upgrading it to
+    /// `finalized` once the second subscription catches up. Slots that never
+    /// finalize are marked `orphaned` instead.
+    DualTrack,
+}
+
+/// Which Solana commitment level a (non-dual-track) fetcher tracks,
+/// configurable via `--commitment`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn to_commitment_config(self) -> CommitmentConfig {
+        match self {
+            Commitment::Processed => CommitmentConfig::processed(),
+            Commitment::Confirmed => CommitmentConfig::confirmed(),
+            Commitment::Finalized => CommitmentConfig::finalized(),
+        }
+    }
+
+    /// The status a transaction observed at this commitment level should be
+    /// stored with.
+    fn as_confirmation_status(self) -> ConfirmationStatus {
+        match self {
+            Commitment::Processed | Commitment::Confirmed => ConfirmationStatus::Confirmed,
+            Commitment::Finalized => ConfirmationStatus::Finalized,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum TransactionFetcherError {
     #[error(transparent)]
@@ -26,43 +83,690 @@ pub enum TransactionFetcherError {
     PubsubClient(#[from] PubsubClientError),
 
     #[error(transparent)]
-    Surrealdb(#[from] surrealdb::Error),
+    Store(#[from] StoreError),
 
     #[error("Fetch Failed")]
     FetchFailed,
+
+    #[error("at least one RPC endpoint is required")]
+    NoRpcEndpoints,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct Transaction {
-    signature: String,
-    slot: u64,
-    block_hash: String,
-    timestamp: i64,
-    data: EncodedConfirmedTransactionWithStatusMeta,
+/// A fetch or store that failed transiently and is eligible for a delayed
+/// retry, as scheduled by `RetryQueue`.
+#[derive(Debug, Clone)]
+enum RetryTask {
+    Block {
+        slot: u64,
+    },
+    Transaction {
+        signature: String,
+        slot: u64,
+        block_hash: String,
+        block_time: i64,
+    },
+    /// A batch of already-fetched transactions whose `store_batch` call
+    /// failed, as produced by `store_block`/`store_confirmed_block`.
+    StoreBatch {
+        transactions: Vec<Transaction>,
+    },
+    /// A single already-fetched transaction whose `upsert_transaction` call
+    /// failed, as produced by `store_finalized_block`.
+    UpsertTransaction {
+        transaction: Transaction,
+    },
 }
 
-pub struct TransactionFetcher<C: Connection> {
-    rpc: RpcClient,
-    ws: PubsubClient,
+/// Per-slot latencies captured by `TransactionFetcher::bench_fetch_slot` or
+/// `bench_store_block`, for the ingestion benchmark harness to aggregate
+/// into a report. `block_latency` measures whichever step is this mode's
+/// actual per-slot cost: the block RPC fetch for the legacy path, or the
+/// bulk store call for a block_subscribe-driven run, where the block
+/// arrives pre-fetched over the websocket instead.
+#[derive(Debug, Clone)]
+pub struct BenchSlotSample {
+    pub block_latency: std::time::Duration,
+    pub transaction_latencies: Vec<std::time::Duration>,
+    pub transactions_stored: usize,
+}
+
+/// The fetch/store/retry machinery, held by `TransactionFetcher` and cheaply
+/// cloned so a failed store can drain its retry queue on a spawned
+/// background task instead of blocking the ingestion loop on the
+/// backoff/retry cycle.
+struct RetryWorker {
+    rpc: Arc<RpcPool>,
     block_config: RpcBlockConfig,
     transaction_config: RpcTransactionConfig,
-    db: Surreal<C>,
-    root_lag: u64,
+    store: Arc<dyn TransactionStore>,
     tx_limit: usize,
+    commitment: Commitment,
+    /// Caps how many transaction fetches may be in flight at once, so a
+    /// thousand-signature block can't open a thousand simultaneous RPC calls.
+    fetch_semaphore: Arc<Semaphore>,
+    metrics: Arc<Metrics>,
+}
+
+impl Clone for RetryWorker {
+    fn clone(&self) -> Self {
+        Self {
+            rpc: self.rpc.clone(),
+            block_config: self.block_config,
+            transaction_config: self.transaction_config,
+            store: self.store.clone(),
+            tx_limit: self.tx_limit,
+            commitment: self.commitment,
+            fetch_semaphore: self.fetch_semaphore.clone(),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl RetryWorker {
+    async fn fetch_latest_slot_from_db(&self) -> Option<u64> {
+        self.store.latest_slot().await
+    }
+
+    /// Fetches `signature`'s transaction details and stores it, acquiring a
+    /// permit from `fetch_semaphore` so a large block can't open unbounded
+    /// concurrent RPC calls. Returns a `RetryTask` if the fetch or store
+    /// failed transiently; invalid signatures are logged and dropped instead,
+    /// since retrying them can never succeed.
+    async fn fetch_transaction(
+        &self,
+        signature: String,
+        slot: u64,
+        block_hash: String,
+        block_time: i64,
+    ) -> Option<RetryTask> {
+        let parsed = match Signature::from_str(&signature) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Invalid signature format: {}", e);
+                return None;
+            }
+        };
+
+        let _permit = self
+            .fetch_semaphore
+            .acquire()
+            .await
+            .expect("fetch semaphore should never be closed");
+
+        let fetch_timer = self.metrics.transaction_fetch_latency.start_timer();
+        let fetch_result = self
+            .rpc
+            .get_transaction_with_config(&parsed, self.transaction_config)
+            .await;
+        fetch_timer.observe_duration();
+
+        match fetch_result {
+            Ok(transaction) => {
+                let content = Transaction {
+                    signature: signature.clone(),
+                    slot,
+                    block_hash: block_hash.clone(),
+                    timestamp: block_time,
+                    confirmation_status: self.commitment.as_confirmation_status(),
+                    data: transaction,
+                };
+
+                let insert_timer = self.metrics.db_insert_latency.start_timer();
+                let insert_result = self.store.store_transaction(content).await;
+                insert_timer.observe_duration();
+
+                match insert_result {
+                    Ok(()) => {
+                        info!("Stored transaction: {}", signature);
+                        self.metrics.transactions_stored.inc();
+                        None
+                    }
+                    Err(e) => {
+                        error!("Failed to store transaction: {}", e);
+                        self.metrics.record_error(ErrorKind::DbWrite);
+                        Some(RetryTask::Transaction {
+                            signature,
+                            slot,
+                            block_hash,
+                            block_time,
+                        })
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Failed to fetch transaction details: {}", e);
+                self.metrics.record_error(ErrorKind::TransactionFetch);
+                Some(RetryTask::Transaction {
+                    signature,
+                    slot,
+                    block_hash,
+                    block_time,
+                })
+            }
+        }
+    }
+
+    /// Fetches every transaction of `slot` and stores it, bounded by
+    /// `fetch_semaphore`. Returns the set of transactions (or the whole slot,
+    /// if the block fetch itself failed) that need a retry.
+    async fn fetch_slot(&self, slot: u64) -> Vec<RetryTask> {
+        let fetch_timer = self.metrics.block_fetch_latency.start_timer();
+        let block_result = self
+            .rpc
+            .get_block_with_config(slot, self.block_config)
+            .await;
+        fetch_timer.observe_duration();
+
+        match block_result {
+            Ok(block) => {
+                self.metrics.blocks_fetched.inc();
+
+                let Some(signatures) = block.signatures else {
+                    return Vec::new();
+                };
+
+                let block_hash = block.blockhash;
+                let block_time = block.block_time.unwrap_or_default();
+                let mut fetch_futures = Vec::new();
+
+                for signature in signatures.into_iter().take(self.tx_limit) {
+                    fetch_futures.push(self.fetch_transaction(
+                        signature,
+                        slot,
+                        block_hash.clone(),
+                        block_time,
+                    ));
+                }
+
+                if fetch_futures.is_empty() {
+                    return Vec::new();
+                }
+
+                join_all(fetch_futures).await.into_iter().flatten().collect()
+            }
+            Err(e) => {
+                error!("Failed to fetch block data for slot {}: {}", slot, e);
+                self.metrics.record_error(ErrorKind::BlockFetch);
+                vec![RetryTask::Block { slot }]
+            }
+        }
+    }
+
+    /// Fetches and stores every transaction of `slot` like `fetch_slot`, but
+    /// one at a time and without `fetch_semaphore` or the retry queue,
+    /// returning the raw per-call latencies instead, for the ingestion
+    /// benchmark harness to aggregate.
+    async fn bench_fetch_slot(&self, slot: u64) -> BenchSlotSample {
+        let fetch_started = std::time::Instant::now();
+        let block_result = self
+            .rpc
+            .get_block_with_config(slot, self.block_config)
+            .await;
+        let block_latency = fetch_started.elapsed();
+
+        let Ok(block) = block_result else {
+            return BenchSlotSample {
+                block_latency,
+                transaction_latencies: Vec::new(),
+                transactions_stored: 0,
+            };
+        };
+
+        let Some(signatures) = block.signatures else {
+            return BenchSlotSample {
+                block_latency,
+                transaction_latencies: Vec::new(),
+                transactions_stored: 0,
+            };
+        };
+
+        let block_hash = block.blockhash;
+        let block_time = block.block_time.unwrap_or_default();
+        let mut transaction_latencies = Vec::with_capacity(signatures.len());
+        let mut transactions_stored = 0;
+
+        for signature in signatures.into_iter().take(self.tx_limit) {
+            let Ok(parsed) = Signature::from_str(&signature) else {
+                continue;
+            };
+
+            let tx_started = std::time::Instant::now();
+            let fetch_result = self
+                .rpc
+                .get_transaction_with_config(&parsed, self.transaction_config)
+                .await;
+            transaction_latencies.push(tx_started.elapsed());
+
+            let Ok(transaction) = fetch_result else {
+                continue;
+            };
+
+            let content = Transaction {
+                signature,
+                slot,
+                block_hash: block_hash.clone(),
+                timestamp: block_time,
+                confirmation_status: self.commitment.as_confirmation_status(),
+                data: transaction,
+            };
+
+            if self.store.store_transaction(content).await.is_ok() {
+                transactions_stored += 1;
+            }
+        }
+
+        BenchSlotSample {
+            block_latency,
+            transaction_latencies,
+            transactions_stored,
+        }
+    }
+
+    /// Maps a block delivered by `block_subscribe` into storable transactions
+    /// and times the bulk insert, the same work `store_block` does, but
+    /// skips the retry queue and returns per-call latencies instead, for the
+    /// ingestion benchmark harness to aggregate.
+    async fn bench_store_block(&self, slot: u64, block: UiConfirmedBlock) -> BenchSlotSample {
+        let empty_sample = BenchSlotSample {
+            block_latency: std::time::Duration::ZERO,
+            transaction_latencies: Vec::new(),
+            transactions_stored: 0,
+        };
+
+        let Some(transactions) = block.transactions else {
+            return empty_sample;
+        };
+
+        let block_hash = block.blockhash;
+        let block_time = block.block_time;
+
+        let mut to_store = Vec::with_capacity(transactions.len());
+        for tx in transactions.into_iter().take(self.tx_limit) {
+            let Some(signature) = first_signature(&tx) else {
+                continue;
+            };
+
+            to_store.push(Transaction {
+                signature,
+                slot,
+                block_hash: block_hash.clone(),
+                timestamp: block_time.unwrap_or_default(),
+                confirmation_status: self.commitment.as_confirmation_status(),
+                data: EncodedConfirmedTransactionWithStatusMeta {
+                    slot,
+                    transaction: tx,
+                    block_time,
+                },
+            });
+        }
+
+        if to_store.is_empty() {
+            return empty_sample;
+        }
+
+        let insert_started = std::time::Instant::now();
+        let insert_result = self.store.store_batch(&to_store).await;
+        let block_latency = insert_started.elapsed();
+
+        BenchSlotSample {
+            block_latency,
+            transaction_latencies: Vec::new(),
+            transactions_stored: insert_result.unwrap_or(0),
+        }
+    }
+
+    /// Schedules `task` for a delayed retry, logging it as a dropped dead
+    /// letter once it has exhausted its retry budget.
+    fn schedule_retry(&self, retry_queue: &mut RetryQueue<RetryTask>, task: RetryTask, attempts: u32) {
+        if let Err(dead) = retry_queue.schedule(task, attempts) {
+            error!(
+                "Dropping {:?} after exhausting retries, recording as dead letter",
+                dead
+            );
+        }
+    }
+
+    /// Re-attempts every task in `retry_queue` as it becomes ready, pushing
+    /// any further failures back onto the queue with a longer backoff, until
+    /// the queue drains or every task has exhausted its retries.
+    async fn drain_retries(&self, mut retry_queue: RetryQueue<RetryTask>) {
+        while let Some((task, attempts)) = retry_queue.next_ready().await {
+            let follow_up = match task {
+                RetryTask::Block { slot } => {
+                    info!("Retrying slot {} (attempt {})", slot, attempts + 1);
+                    self.fetch_slot(slot).await
+                }
+                RetryTask::Transaction {
+                    signature,
+                    slot,
+                    block_hash,
+                    block_time,
+                } => {
+                    info!(
+                        "Retrying transaction {} (attempt {})",
+                        signature,
+                        attempts + 1
+                    );
+                    self.fetch_transaction(signature, slot, block_hash, block_time)
+                        .await
+                        .into_iter()
+                        .collect()
+                }
+                RetryTask::StoreBatch { transactions } => {
+                    info!(
+                        "Retrying store of {} transactions (attempt {})",
+                        transactions.len(),
+                        attempts + 1
+                    );
+                    match self.store.store_batch(&transactions).await {
+                        Ok(written) => {
+                            self.metrics.transactions_stored.inc_by(written as u64);
+                            Vec::new()
+                        }
+                        Err(e) => {
+                            error!("Retry of store_batch failed: {}", e);
+                            self.metrics.record_error(ErrorKind::DbWrite);
+                            vec![RetryTask::StoreBatch { transactions }]
+                        }
+                    }
+                }
+                RetryTask::UpsertTransaction { transaction } => {
+                    info!(
+                        "Retrying upsert of transaction {} (attempt {})",
+                        transaction.signature,
+                        attempts + 1
+                    );
+                    match self.store.upsert_transaction(transaction.clone()).await {
+                        Ok(()) => {
+                            self.metrics.transactions_stored.inc();
+                            Vec::new()
+                        }
+                        Err(e) => {
+                            error!("Retry of upsert_transaction failed: {}", e);
+                            self.metrics.record_error(ErrorKind::DbWrite);
+                            vec![RetryTask::UpsertTransaction { transaction }]
+                        }
+                    }
+                }
+            };
+
+            for task in follow_up {
+                self.schedule_retry(&mut retry_queue, task, attempts + 1);
+            }
+        }
+    }
+
+    /// Spawns `drain_retries` on a background task instead of awaiting it
+    /// inline, so a run of transient DB hiccups backs off and retries on its
+    /// own schedule without stalling the ingestion loop that queued it — a
+    /// single dropped block_subscribe/dual-track notification can otherwise
+    /// hold up every subsequent one for the length of the whole backoff chain.
+    fn spawn_drain_retries(&self, retry_queue: RetryQueue<RetryTask>) {
+        let worker = self.clone();
+        tokio::spawn(async move { worker.drain_retries(retry_queue).await });
+    }
+
+    async fn fetch_range(&self, from_slot: u64, to_slot: u64) {
+        let mut retry_queue = RetryQueue::new();
+
+        for slot in from_slot..=to_slot {
+            for task in self.fetch_slot(slot).await {
+                self.schedule_retry(&mut retry_queue, task, 1);
+            }
+        }
+
+        self.drain_retries(retry_queue).await;
+    }
+
+    /// Fetches a full block at `commitment` over RPC, for backfilling a slot
+    /// dual-track's subscriptions skipped.
+    async fn fetch_full_block(&self, slot: u64, commitment: CommitmentConfig) -> Option<UiConfirmedBlock> {
+        let config = RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::JsonParsed),
+            transaction_details: Some(TransactionDetails::Full),
+            rewards: Some(false),
+            commitment: Some(commitment),
+            max_supported_transaction_version: Some(0),
+        };
+
+        match self.rpc.get_block_with_config(slot, config).await {
+            Ok(block) => Some(block),
+            Err(e) => {
+                error!("Failed to backfill block for slot {}: {}", slot, e);
+                self.metrics.record_error(ErrorKind::BlockFetch);
+                None
+            }
+        }
+    }
+
+    /// Backfills `[from_slot, to_slot]` at `confirmed` commitment, routing
+    /// each slot through `store_confirmed_block` the same as the live
+    /// subscription does.
+    async fn backfill_confirmed(&self, from_slot: u64, to_slot: u64) {
+        for slot in from_slot..=to_slot {
+            match self.fetch_full_block(slot, CommitmentConfig::confirmed()).await {
+                Some(block) => self.store_confirmed_block(slot, block).await,
+                None => warn!("Could not backfill confirmed slot {}, leaving it missing", slot),
+            }
+        }
+    }
+
+    /// Backfills `[from_slot, to_slot]` at `finalized` commitment, routing
+    /// each slot through `store_finalized_block` the same as the live
+    /// subscription does.
+    async fn backfill_finalized(&self, from_slot: u64, to_slot: u64) {
+        for slot in from_slot..=to_slot {
+            match self.fetch_full_block(slot, CommitmentConfig::finalized()).await {
+                Some(block) => self.store_finalized_block(slot, block).await,
+                None => warn!("Could not backfill finalized slot {}, leaving it missing", slot),
+            }
+        }
+    }
+
+    /// Maps every transaction in a subscribed block directly into `Transaction`
+    /// rows and bulk-inserts them, without any per-signature RPC call.
+    async fn store_block(&self, slot: u64, block: UiConfirmedBlock) {
+        let Some(transactions) = block.transactions else {
+            return;
+        };
+
+        let block_hash = block.blockhash;
+        let block_time = block.block_time;
+
+        let mut to_store = Vec::with_capacity(transactions.len());
+        for tx in transactions.into_iter().take(self.tx_limit) {
+            let Some(signature) = first_signature(&tx) else {
+                warn!("Skipping transaction with no signature in slot {}", slot);
+                continue;
+            };
+
+            to_store.push(Transaction {
+                signature,
+                slot,
+                block_hash: block_hash.clone(),
+                timestamp: block_time.unwrap_or_default(),
+                confirmation_status: self.commitment.as_confirmation_status(),
+                data: EncodedConfirmedTransactionWithStatusMeta {
+                    slot,
+                    transaction: tx,
+                    block_time,
+                },
+            });
+        }
+
+        if to_store.is_empty() {
+            return;
+        }
+
+        let stored = to_store.len();
+        let insert_timer = self.metrics.db_insert_latency.start_timer();
+        let insert_result = self.store.store_batch(&to_store).await;
+        insert_timer.observe_duration();
+
+        match insert_result {
+            Ok(written) => {
+                info!("Stored {}/{} transactions for slot {}", written, stored, slot);
+                self.metrics.blocks_fetched.inc();
+                self.metrics.transactions_stored.inc_by(written as u64);
+            }
+            Err(e) => {
+                error!("Failed to store transactions for slot {}: {}", slot, e);
+                self.metrics.record_error(ErrorKind::DbWrite);
+                let mut retry_queue = RetryQueue::new();
+                self.schedule_retry(
+                    &mut retry_queue,
+                    RetryTask::StoreBatch { transactions: to_store },
+                    1,
+                );
+                self.spawn_drain_retries(retry_queue);
+            }
+        }
+    }
+
+    /// Bulk-inserts a confirmed block's transactions as `confirmed` rows,
+    /// which `store_finalized_block` later upgrades in place once the slot
+    /// finalizes.
+    async fn store_confirmed_block(&self, slot: u64, block: UiConfirmedBlock) {
+        let Some(transactions) = block.transactions else {
+            return;
+        };
+
+        let block_hash = block.blockhash;
+        let block_time = block.block_time;
+
+        let mut to_store = Vec::with_capacity(transactions.len());
+        for tx in transactions.into_iter().take(self.tx_limit) {
+            let Some(signature) = first_signature(&tx) else {
+                warn!("Skipping transaction with no signature in slot {}", slot);
+                continue;
+            };
+
+            to_store.push(Transaction {
+                signature,
+                slot,
+                block_hash: block_hash.clone(),
+                timestamp: block_time.unwrap_or_default(),
+                confirmation_status: ConfirmationStatus::Confirmed,
+                data: EncodedConfirmedTransactionWithStatusMeta {
+                    slot,
+                    transaction: tx,
+                    block_time,
+                },
+            });
+        }
+
+        if to_store.is_empty() {
+            return;
+        }
+
+        let stored = to_store.len();
+        let insert_timer = self.metrics.db_insert_latency.start_timer();
+        let insert_result = self.store.store_batch(&to_store).await;
+        insert_timer.observe_duration();
+
+        match insert_result {
+            Ok(written) => {
+                info!(
+                    "Stored {}/{} confirmed transactions for slot {}",
+                    written, stored, slot
+                );
+                self.metrics.blocks_fetched.inc();
+                self.metrics.transactions_stored.inc_by(written as u64);
+            }
+            Err(e) => {
+                error!("Failed to store confirmed transactions for slot {}: {}", slot, e);
+                self.metrics.record_error(ErrorKind::DbWrite);
+                let mut retry_queue = RetryQueue::new();
+                self.schedule_retry(
+                    &mut retry_queue,
+                    RetryTask::StoreBatch { transactions: to_store },
+                    1,
+                );
+                self.spawn_drain_retries(retry_queue);
+            }
+        }
+    }
+
+    /// Upserts a finalized block's transactions one at a time, upgrading any
+    /// `confirmed` rows the confirmed leg already wrote for this slot.
+    async fn store_finalized_block(&self, slot: u64, block: UiConfirmedBlock) {
+        let Some(transactions) = block.transactions else {
+            return;
+        };
+
+        let block_hash = block.blockhash;
+        let block_time = block.block_time;
+        let mut retry_queue = RetryQueue::new();
+
+        for tx in transactions.into_iter().take(self.tx_limit) {
+            let Some(signature) = first_signature(&tx) else {
+                warn!("Skipping transaction with no signature in slot {}", slot);
+                continue;
+            };
+
+            let transaction = Transaction {
+                signature: signature.clone(),
+                slot,
+                block_hash: block_hash.clone(),
+                timestamp: block_time.unwrap_or_default(),
+                confirmation_status: ConfirmationStatus::Finalized,
+                data: EncodedConfirmedTransactionWithStatusMeta {
+                    slot,
+                    transaction: tx,
+                    block_time,
+                },
+            };
+
+            let insert_timer = self.metrics.db_insert_latency.start_timer();
+            let insert_result = self.store.upsert_transaction(transaction.clone()).await;
+            insert_timer.observe_duration();
+
+            match insert_result {
+                Ok(()) => self.metrics.transactions_stored.inc(),
+                Err(e) => {
+                    error!("Failed to upsert finalized transaction {}: {}", signature, e);
+                    self.metrics.record_error(ErrorKind::DbWrite);
+                    self.schedule_retry(&mut retry_queue, RetryTask::UpsertTransaction { transaction }, 1);
+                }
+            }
+        }
+
+        if !retry_queue.is_empty() {
+            self.spawn_drain_retries(retry_queue);
+        }
+
+        self.metrics.blocks_fetched.inc();
+        info!("Finalized slot {}", slot);
+    }
+}
+
+pub struct TransactionFetcher {
+    ws: PubsubClient,
+    retry: RetryWorker,
+    root_lag: u64,
+    ingestion_mode: IngestionMode,
     shutdown: broadcast::Receiver<()>,
 }
 
-impl<C: Connection> TransactionFetcher<C> {
+impl TransactionFetcher {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
-        rpc_url: Url,
+        rpc_urls: Vec<Url>,
         ws_url: Url,
-        db: Surreal<C>,
+        store: Arc<dyn TransactionStore>,
         root_lag: u64,
         tx_limit: usize,
+        ingestion_mode: IngestionMode,
+        commitment: Commitment,
+        max_concurrent_fetches: usize,
+        metrics: Arc<Metrics>,
         shutdown: broadcast::Receiver<()>,
     ) -> Result<Self, TransactionFetcherError> {
-        let rpc =
-            RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::finalized());
+        if rpc_urls.is_empty() {
+            return Err(TransactionFetcherError::NoRpcEndpoints);
+        }
+
+        let rpc = Arc::new(RpcPool::new(&rpc_urls, commitment.to_commitment_config()));
         let ws = PubsubClient::new(ws_url.as_str()).await?;
         let block_config = RpcBlockConfig {
             encoding: Some(UiTransactionEncoding::JsonParsed),
@@ -73,121 +777,141 @@ impl<C: Connection> TransactionFetcher<C> {
         };
         let transaction_config = RpcTransactionConfig {
             encoding: Some(UiTransactionEncoding::JsonParsed),
-            commitment: Some(CommitmentConfig::finalized()),
+            commitment: Some(commitment.to_commitment_config()),
             max_supported_transaction_version: Some(0),
         };
 
         Ok(Self {
-            rpc,
             ws,
-            block_config,
-            transaction_config,
-            db,
+            retry: RetryWorker {
+                rpc,
+                block_config,
+                transaction_config,
+                store,
+                tx_limit,
+                commitment,
+                fetch_semaphore: Arc::new(Semaphore::new(max_concurrent_fetches.max(1))),
+                metrics,
+            },
             root_lag,
-            tx_limit,
+            ingestion_mode,
             shutdown,
         })
     }
 
-    async fn fetch_latest_slot_from_db(&self) -> Option<u64> {
-        #[derive(Debug, Deserialize)]
-        struct SlotResult {
-            slot: u64,
-        }
+    /// Which ingestion path `run` would dispatch to, so the bench harness can
+    /// pick a matching way to drive slots through the pipeline.
+    pub fn ingestion_mode(&self) -> IngestionMode {
+        self.ingestion_mode
+    }
 
-        match self
-            .db
-            .query("SELECT slot FROM transactions ORDER BY slot DESC LIMIT 1")
-            .await
-        {
-            Ok(mut res) => {
-                if let Some(slot) = res.take::<Option<SlotResult>>(0).ok().flatten() {
-                    info!("Recovered latest slot from database: {}", slot.slot);
-                    Some(slot.slot)
-                } else {
-                    warn!("No slot data found in database, starting from next received slot.");
-                    None
-                }
-            }
-            Err(e) => {
-                error!("Failed to fetch latest slot from database: {}", e);
-                None
-            }
+    /// The commitment level subscriptions/fetches use, so the bench harness
+    /// can mirror it when opening its own subscription.
+    pub fn commitment(&self) -> Commitment {
+        self.retry.commitment
+    }
+
+    /// Fetches and stores every transaction of `slot` like `fetch_slot`, but
+    /// one at a time and without the fetch concurrency cap or the retry
+    /// queue, returning the raw per-call latencies instead, for the ingestion
+    /// benchmark harness to aggregate.
+    pub async fn bench_fetch_slot(&self, slot: u64) -> BenchSlotSample {
+        self.retry.bench_fetch_slot(slot).await
+    }
+
+    /// Maps a block delivered by `block_subscribe` into storable transactions
+    /// and times the bulk insert, the same work `store_block` does, but
+    /// skips the retry queue and returns per-call latencies instead, for the
+    /// ingestion benchmark harness to aggregate.
+    pub async fn bench_store_block(&self, slot: u64, block: UiConfirmedBlock) -> BenchSlotSample {
+        self.retry.bench_store_block(slot, block).await
+    }
+
+    pub async fn run(&mut self) -> Result<(), TransactionFetcherError> {
+        match self.ingestion_mode {
+            IngestionMode::BlockSubscribe => self.run_block_subscribe().await,
+            IngestionMode::RootSubscribe => self.run_root_subscribe().await,
+            IngestionMode::DualTrack => self.run_dual_track().await,
         }
     }
 
-    async fn fetch_range(&self, from_slot: u64, to_slot: u64) {
-        for slot in from_slot..=to_slot {
-            match self
-                .rpc
-                .get_block_with_config(slot, self.block_config)
-                .await
-            {
-                Ok(block) => {
-                    if let Some(signatures) = block.signatures {
-                        let mut fetch_futures = Vec::new();
-
-                        for signature in signatures.into_iter().take(self.tx_limit) {
-                            let db = self.db.clone();
-                            let transaction_config = self.transaction_config;
-                            let block_hash = block.blockhash.clone();
-
-                            fetch_futures.push(async move {
-                                match Signature::from_str(&signature) {
-                                    Ok(s) => match self
-                                        .rpc
-                                        .get_transaction_with_config(&s, transaction_config)
-                                        .await
-                                    {
-                                        Ok(transaction) => {
-                                            let content = Transaction {
-                                                signature,
-                                                slot,
-                                                block_hash,
-                                                timestamp: block.block_time.unwrap_or_default(),
-                                                data: transaction,
-                                            };
-
-                                            #[derive(Debug, Deserialize)]
-                                            struct Id {
-                                                #[allow(dead_code)]
-                                                id: RecordId,
-                                            }
-
-                                            match db
-                                                .create::<Option<Id>>("transactions")
-                                                .content(content)
-                                                .await
-                                            {
-                                                Ok(id) => info!("Stored transaction: {:?}", id),
-                                                Err(e) => {
-                                                    error!("Failed to store transaction: {}", e)
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            error!("Failed to fetch transaction details: {}", e)
-                                        }
-                                    },
-                                    Err(e) => error!("Invalid signature format: {}", e),
-                                }
-                            });
-                        }
+    /// Ingests transactions by subscribing to full blocks over `block_subscribe`,
+    /// storing every transaction directly from the notification. Falls back to
+    /// `fetch_range` only to backfill slots skipped by the subscription.
+    async fn run_block_subscribe(&mut self) -> Result<(), TransactionFetcherError> {
+        let (mut stream, unsubscribe) = self
+            .ws
+            .block_subscribe(
+                RpcBlockSubscribeFilter::All,
+                Some(RpcBlockSubscribeConfig {
+                    commitment: Some(self.retry.commitment.to_commitment_config()),
+                    encoding: Some(UiTransactionEncoding::JsonParsed),
+                    transaction_details: Some(TransactionDetails::Full),
+                    show_rewards: Some(false),
+                    max_supported_transaction_version: Some(0),
+                }),
+            )
+            .await?;
 
-                        if !fetch_futures.is_empty() {
-                            join_all(fetch_futures).await;
+        let mut next_slot = self.retry.fetch_latest_slot_from_db().await.map(|slot| slot + 1);
+
+        loop {
+            select! {
+                Some(update) = stream.next() => {
+                    let slot = update.value.slot;
+                    self.retry.metrics.slot_lag.set(slot as i64 - next_slot.unwrap_or(slot) as i64);
+
+                    if let Some(expected) = next_slot {
+                        if slot > expected {
+                            warn!(
+                                "Detected gap before slot {}, backfilling from {}",
+                                slot, expected
+                            );
+                            self.retry.fetch_range(expected, slot - 1).await;
                         }
                     }
+
+                    match update.value.err {
+                        Some(e) => warn!("block_subscribe reported an error for slot {}: {:?}", slot, e),
+                        None => match update.value.block {
+                            Some(block) => self.retry.store_block(slot, block).await,
+                            None => {
+                                warn!(
+                                    "block_subscribe notification for slot {} carried no block, backfilling",
+                                    slot
+                                );
+                                self.retry.fetch_range(slot, slot).await;
+                            }
+                        },
+                    }
+
+                    // `max` guards against an out-of-order or duplicate notification
+                    // rewinding next_slot, which would corrupt the gap check above.
+                    next_slot = Some(next_slot.map_or(slot, |expected| expected.max(slot)) + 1);
+                },
+                Ok(_) = self.shutdown.recv() => {
+                    info!("Shutdown signal received.");
+                    break;
+                }
+                else => {
+                    error!("Unexpected error in block subscribe loop, shutting down.");
+                    break;
                 }
-                Err(e) => error!("Failed to fetch block data for slot {}: {}", slot, e),
             }
         }
+
+        info!("Fetcher shutting down...");
+        unsubscribe().await;
+        info!("Fetcher shut down.");
+        Ok(())
     }
 
-    pub async fn run(&mut self) -> Result<(), TransactionFetcherError> {
+    /// Ingests transactions the original way: subscribe to new roots, then
+    /// fetch each slot (and every transaction in it) over RPC.
+    async fn run_root_subscribe(&mut self) -> Result<(), TransactionFetcherError> {
         let (mut stream, unsubscribe) = self.ws.root_subscribe().await?;
 
-        let mut next_slot = match self.fetch_latest_slot_from_db().await {
+        let mut next_slot = match self.retry.fetch_latest_slot_from_db().await {
             Some(slot) => slot + 1,
             None => {
                 let slot = stream
@@ -203,10 +927,11 @@ impl<C: Connection> TransactionFetcher<C> {
             select! {
                 Some(slot) = stream.next() => {
                     let adjusted_slot = slot.saturating_sub(self.root_lag);
+                    self.retry.metrics.slot_lag.set(adjusted_slot as i64 - next_slot as i64);
 
                     if adjusted_slot >= next_slot {
                         info!("Fetching missing transactions from slot {} to {}", next_slot, adjusted_slot);
-                        self.fetch_range(next_slot, adjusted_slot).await;
+                        self.retry.fetch_range(next_slot, adjusted_slot).await;
                     } else {
                         warn!("Skipping redundant fetch for slot {}", adjusted_slot);
                     }
@@ -229,4 +954,148 @@ impl<C: Connection> TransactionFetcher<C> {
         info!("Fetcher shut down.");
         Ok(())
     }
+
+    /// Ingests transactions by subscribing to blocks at both `confirmed` and
+    /// `finalized` commitment. Confirmed blocks are stored right away;
+    /// finalized blocks upgrade those rows in place. A confirmed slot that
+    /// falls more than `root_lag` slots behind the latest finalized slot
+    /// without itself finalizing was dropped on a fork, and is marked
+    /// `orphaned` instead. Both legs resume from the last persisted slot on
+    /// restart and backfill over RPC whenever either subscription skips
+    /// ahead, the same way `run_block_subscribe` does for its single stream.
+    async fn run_dual_track(&mut self) -> Result<(), TransactionFetcherError> {
+        let (mut confirmed_stream, confirmed_unsubscribe) = self
+            .ws
+            .block_subscribe(
+                RpcBlockSubscribeFilter::All,
+                Some(RpcBlockSubscribeConfig {
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    encoding: Some(UiTransactionEncoding::JsonParsed),
+                    transaction_details: Some(TransactionDetails::Full),
+                    show_rewards: Some(false),
+                    max_supported_transaction_version: Some(0),
+                }),
+            )
+            .await?;
+
+        let (mut finalized_stream, finalized_unsubscribe) = self
+            .ws
+            .block_subscribe(
+                RpcBlockSubscribeFilter::All,
+                Some(RpcBlockSubscribeConfig {
+                    commitment: Some(CommitmentConfig::finalized()),
+                    encoding: Some(UiTransactionEncoding::JsonParsed),
+                    transaction_details: Some(TransactionDetails::Full),
+                    show_rewards: Some(false),
+                    max_supported_transaction_version: Some(0),
+                }),
+            )
+            .await?;
+
+        // Confirmed slots not yet finalized or orphaned, oldest first.
+        let mut pending: BTreeSet<u64> = BTreeSet::new();
+        let mut latest_finalized: Option<u64> = None;
+
+        let resume_from = self.retry.fetch_latest_slot_from_db().await.map(|slot| slot + 1);
+        let mut next_confirmed_slot = resume_from;
+        let mut next_finalized_slot = resume_from;
+
+        loop {
+            select! {
+                Some(update) = confirmed_stream.next() => {
+                    let slot = update.value.slot;
+
+                    if let Some(expected) = next_confirmed_slot {
+                        if slot > expected {
+                            warn!(
+                                "Detected gap before confirmed slot {}, backfilling from {}",
+                                slot, expected
+                            );
+                            self.retry.backfill_confirmed(expected, slot - 1).await;
+                            pending.extend(expected..slot);
+                        }
+                    }
+                    next_confirmed_slot = Some(next_confirmed_slot.map_or(slot, |expected| expected.max(slot)) + 1);
+
+                    match update.value.err {
+                        Some(e) => warn!(
+                            "confirmed block_subscribe reported an error for slot {}: {:?}",
+                            slot, e
+                        ),
+                        None => if let Some(block) = update.value.block {
+                            self.retry.store_confirmed_block(slot, block).await;
+                            pending.insert(slot);
+                        },
+                    }
+
+                    if let Some(finalized) = latest_finalized {
+                        let stale: Vec<u64> = pending
+                            .iter()
+                            .copied()
+                            .take_while(|&s| s + self.root_lag < finalized)
+                            .collect();
+
+                        for stale_slot in stale {
+                            warn!("Slot {} never finalized, marking orphaned", stale_slot);
+                            if let Err(e) = self.retry.store.mark_orphaned(stale_slot).await {
+                                error!("Failed to mark slot {} as orphaned: {}", stale_slot, e);
+                                self.retry.metrics.record_error(ErrorKind::DbWrite);
+                            }
+                            pending.remove(&stale_slot);
+                        }
+                    }
+                },
+                Some(update) = finalized_stream.next() => {
+                    let slot = update.value.slot;
+
+                    if let Some(expected) = next_finalized_slot {
+                        if slot > expected {
+                            warn!(
+                                "Detected gap before finalized slot {}, backfilling from {}",
+                                slot, expected
+                            );
+                            self.retry.backfill_finalized(expected, slot - 1).await;
+                        }
+                    }
+                    next_finalized_slot = Some(next_finalized_slot.map_or(slot, |expected| expected.max(slot)) + 1);
+
+                    latest_finalized = Some(slot);
+                    pending.remove(&slot);
+
+                    match update.value.err {
+                        Some(e) => warn!(
+                            "finalized block_subscribe reported an error for slot {}: {:?}",
+                            slot, e
+                        ),
+                        None => if let Some(block) = update.value.block {
+                            self.retry.store_finalized_block(slot, block).await;
+                        },
+                    }
+                },
+                Ok(_) = self.shutdown.recv() => {
+                    info!("Shutdown signal received.");
+                    break;
+                }
+                else => {
+                    error!("Unexpected error in dual-track subscribe loop, shutting down.");
+                    break;
+                }
+            }
+        }
+
+        info!("Fetcher shutting down...");
+        confirmed_unsubscribe().await;
+        finalized_unsubscribe().await;
+        info!("Fetcher shut down.");
+        Ok(())
+    }
+}
+
+/// Pulls the first (fee payer) signature out of a block-subscribe transaction,
+/// which is what identifies it in the `transactions` table.
+fn first_signature(tx: &EncodedTransactionWithStatusMeta) -> Option<String> {
+    match &tx.transaction {
+        EncodedTransaction::Json(ui_tx) => ui_tx.signatures.first().cloned(),
+        _ => None,
+    }
 }
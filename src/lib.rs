@@ -0,0 +1,8 @@
+pub mod bench;
+pub mod fetcher;
+pub mod metrics;
+pub mod retry;
+pub mod rpc_pool;
+pub mod server;
+pub mod shutdown;
+pub mod store;
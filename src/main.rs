@@ -1,21 +1,24 @@
 use std::net::SocketAddr;
 
 use clap::Parser;
-use fetcher::{TransactionFetcher, TransactionFetcherError};
-use surrealdb::{engine::remote::ws::Ws, opt::auth::Root, Surreal};
+use solana_data_aggregator::{
+    fetcher::{Commitment, IngestionMode, TransactionFetcher, TransactionFetcherError},
+    metrics::Metrics,
+    server,
+    shutdown,
+    store::{DbArgs, DbConnectError},
+};
 use thiserror::Error;
 use tokio::{join, net::TcpListener, sync::broadcast};
 use tracing::{error, info, Level};
 use url::Url;
 
-pub mod fetcher;
-pub mod server;
-pub mod shutdown;
-
 #[derive(Debug, Parser)]
 struct Args {
-    #[arg(long, short, env)]
-    rpc_url: Url,
+    /// Comma-separated list of RPC endpoints. Calls are routed to the
+    /// healthiest endpoint, with automatic failover on error.
+    #[arg(long, short = 'r', env, value_delimiter = ',')]
+    rpc_urls: Vec<Url>,
 
     #[arg(long, short, env)]
     ws_url: Url,
@@ -23,32 +26,31 @@ struct Args {
     #[arg(long, short, env)]
     bind: SocketAddr,
 
-    #[arg(long, short = 'a', env)]
-    db_addr: String,
-
-    #[arg(long, short = 'u', env)]
-    db_user: String,
-
-    #[arg(long, short = 'p', env)]
-    db_pass: String,
-
-    #[arg(long, env)]
-    db_ns: String,
-
-    #[arg(long, env)]
-    db_db: String,
+    #[command(flatten)]
+    db: DbArgs,
 
     #[arg(long, short = 'l', env, default_value_t = 100)]
     root_lag: u64,
 
     #[arg(long, short, env, default_value_t = 1000)]
     tx_limit: usize,
+
+    #[arg(long, env, value_enum, default_value_t = IngestionMode::BlockSubscribe)]
+    ingestion_mode: IngestionMode,
+
+    /// Commitment level to track when not using `--ingestion-mode dual-track`,
+    /// which always tracks both `confirmed` and `finalized`.
+    #[arg(long, env, value_enum, default_value_t = Commitment::Finalized)]
+    commitment: Commitment,
+
+    #[arg(long, env, default_value_t = 5)]
+    max_concurrent_fetches: usize,
 }
 
 #[derive(Debug, Error)]
 enum AppError {
     #[error(transparent)]
-    Surrealdb(#[from] surrealdb::Error),
+    Db(#[from] DbConnectError),
 
     #[error(transparent)]
     TransactionFetcher(#[from] TransactionFetcherError),
@@ -67,21 +69,19 @@ async fn main() -> Result<(), AppError> {
     info!("Starting server on {}", args.bind);
     let listener = TcpListener::bind(args.bind).await?;
 
-    info!("Connecting to database at {}", args.db_addr);
-    let db = Surreal::new::<Ws>(&args.db_addr).await?;
-    db.signin(Root {
-        username: &args.db_user,
-        password: &args.db_pass,
-    })
-    .await?;
-    db.use_ns(&args.db_ns).use_db(&args.db_db).await?;
+    let store = args.db.connect().await?;
+    let metrics = Metrics::new();
 
     let mut transaction_fetcher = TransactionFetcher::new(
-        args.rpc_url,
+        args.rpc_urls,
         args.ws_url,
-        db.clone(),
+        store.clone(),
         args.root_lag,
         args.tx_limit,
+        args.ingestion_mode,
+        args.commitment,
+        args.max_concurrent_fetches,
+        metrics.clone(),
         shutdown_tx.subscribe(),
     )
     .await?;
@@ -89,7 +89,7 @@ async fn main() -> Result<(), AppError> {
     info!("Starting tasks...");
     let result = join!(
         transaction_fetcher.run(),
-        server::run(listener, db, shutdown_tx.subscribe()),
+        server::run(listener, store, metrics, shutdown_tx.subscribe()),
         async {
             shutdown::shutdown_signal().await;
             if let Err(e) = shutdown_tx.send(()) {
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+
+/// Kinds of error tracked by the `rpc_errors_total` counter.
+#[derive(Debug, Clone, Copy)]
+pub enum ErrorKind {
+    BlockFetch,
+    TransactionFetch,
+    DbWrite,
+    DbQuery,
+}
+
+impl ErrorKind {
+    fn as_label(self) -> &'static str {
+        match self {
+            ErrorKind::BlockFetch => "block_fetch",
+            ErrorKind::TransactionFetch => "transaction_fetch",
+            ErrorKind::DbWrite => "db_write",
+            ErrorKind::DbQuery => "db_query",
+        }
+    }
+}
+
+/// Prometheus registry and instruments shared between `TransactionFetcher`
+/// and the axum server, exposed together over `/metrics`.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    pub transactions_stored: IntCounter,
+    pub blocks_fetched: IntCounter,
+    rpc_errors: IntCounterVec,
+    pub slot_lag: IntGauge,
+    pub block_fetch_latency: Histogram,
+    pub transaction_fetch_latency: Histogram,
+    pub db_insert_latency: Histogram,
+    pub db_query_latency: Histogram,
+}
+
+impl Metrics {
+    pub fn new() -> Arc<Self> {
+        let registry = Registry::new();
+
+        let transactions_stored = IntCounter::new(
+            "transactions_stored_total",
+            "Number of transactions persisted to the database",
+        )
+        .expect("metric creation should not fail");
+        let blocks_fetched = IntCounter::new(
+            "blocks_fetched_total",
+            "Number of blocks fetched from the RPC or subscription",
+        )
+        .expect("metric creation should not fail");
+        let rpc_errors = IntCounterVec::new(
+            Opts::new(
+                "rpc_errors_total",
+                "RPC and database errors encountered during ingestion and serving, by kind",
+            ),
+            &["kind"],
+        )
+        .expect("metric creation should not fail");
+        let slot_lag = IntGauge::new(
+            "slot_lag",
+            "Gap between the latest slot seen on the websocket and the next slot to be fetched",
+        )
+        .expect("metric creation should not fail");
+        let block_fetch_latency = Histogram::with_opts(
+            HistogramOpts::new(
+                "block_fetch_latency_seconds",
+                "Time to fetch a block over RPC",
+            )
+            .buckets(vec![0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+        )
+        .expect("metric creation should not fail");
+        let transaction_fetch_latency = Histogram::with_opts(
+            HistogramOpts::new(
+                "transaction_fetch_latency_seconds",
+                "Time to fetch a single transaction over RPC",
+            )
+            .buckets(vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5]),
+        )
+        .expect("metric creation should not fail");
+        let db_insert_latency = Histogram::with_opts(
+            HistogramOpts::new(
+                "db_insert_latency_seconds",
+                "Time to write a transaction row to the database",
+            )
+            .buckets(vec![0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5]),
+        )
+        .expect("metric creation should not fail");
+        let db_query_latency = Histogram::with_opts(
+            HistogramOpts::new(
+                "db_query_latency_seconds",
+                "Time to answer a /transactions query from the database",
+            )
+            .buckets(vec![0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5]),
+        )
+        .expect("metric creation should not fail");
+
+        registry
+            .register(Box::new(transactions_stored.clone()))
+            .expect("metric registration should not fail");
+        registry
+            .register(Box::new(blocks_fetched.clone()))
+            .expect("metric registration should not fail");
+        registry
+            .register(Box::new(rpc_errors.clone()))
+            .expect("metric registration should not fail");
+        registry
+            .register(Box::new(slot_lag.clone()))
+            .expect("metric registration should not fail");
+        registry
+            .register(Box::new(block_fetch_latency.clone()))
+            .expect("metric registration should not fail");
+        registry
+            .register(Box::new(transaction_fetch_latency.clone()))
+            .expect("metric registration should not fail");
+        registry
+            .register(Box::new(db_insert_latency.clone()))
+            .expect("metric registration should not fail");
+        registry
+            .register(Box::new(db_query_latency.clone()))
+            .expect("metric registration should not fail");
+
+        Arc::new(Self {
+            registry,
+            transactions_stored,
+            blocks_fetched,
+            rpc_errors,
+            slot_lag,
+            block_fetch_latency,
+            transaction_fetch_latency,
+            db_insert_latency,
+            db_query_latency,
+        })
+    }
+
+    pub fn record_error(&self, kind: ErrorKind) {
+        self.rpc_errors.with_label_values(&[kind.as_label()]).inc();
+    }
+
+    /// Renders every registered metric in the Prometheus text exposition
+    /// format, for the `/metrics` endpoint.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        let encoder = TextEncoder::new();
+        encoder
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("encoding metrics should not fail");
+        String::from_utf8(buffer).expect("prometheus output should be valid utf8")
+    }
+}
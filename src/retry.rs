@@ -0,0 +1,139 @@
+use std::{
+    cmp::Ordering,
+    collections::BinaryHeap,
+    time::{Duration, Instant},
+};
+
+/// Maximum number of attempts (including the first) before a task is
+/// considered permanently failed and dropped as a dead letter.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+struct Entry<T> {
+    ready_at: Instant,
+    attempts: u32,
+    task: T,
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ready_at == other.ready_at
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap`, a max-heap, pops the soonest `ready_at` first.
+        other.ready_at.cmp(&self.ready_at)
+    }
+}
+
+/// A delayed re-queue for fetch/store tasks that failed transiently. Each
+/// scheduled task waits out an exponential backoff before becoming ready
+/// again; tasks that exhaust [`MAX_ATTEMPTS`] are handed back to the caller
+/// as dead letters instead of being retried forever.
+pub struct RetryQueue<T> {
+    heap: BinaryHeap<Entry<T>>,
+}
+
+impl<T> Default for RetryQueue<T> {
+    fn default() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+        }
+    }
+}
+
+impl<T> RetryQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Schedules `task` for retry after backing off for `attempts` (the
+    /// number of attempts already made, including the one that just failed).
+    /// Returns `Err(task)` if `attempts` has reached [`MAX_ATTEMPTS`], so the
+    /// caller can log it as a dead letter.
+    pub fn schedule(&mut self, task: T, attempts: u32) -> Result<(), T> {
+        if attempts >= MAX_ATTEMPTS {
+            return Err(task);
+        }
+
+        let backoff = BASE_BACKOFF
+            .saturating_mul(1 << attempts.min(6))
+            .min(MAX_BACKOFF);
+
+        self.heap.push(Entry {
+            ready_at: Instant::now() + backoff,
+            attempts,
+            task,
+        });
+
+        Ok(())
+    }
+
+    /// Waits for the next scheduled task to become ready and pops it along
+    /// with the number of attempts already made. Returns `None` once the
+    /// queue is empty.
+    pub async fn next_ready(&mut self) -> Option<(T, u32)> {
+        let ready_at = self.heap.peek()?.ready_at;
+        tokio::time::sleep_until(tokio::time::Instant::from_std(ready_at)).await;
+        self.heap.pop().map(|entry| (entry.task, entry.attempts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schedule_rejects_exhausted_attempts() {
+        let mut queue: RetryQueue<&'static str> = RetryQueue::new();
+        assert_eq!(queue.schedule("dead", MAX_ATTEMPTS), Err("dead"));
+        assert_eq!(queue.schedule("also-dead", MAX_ATTEMPTS + 1), Err("also-dead"));
+    }
+
+    #[test]
+    fn schedule_accepts_attempts_below_the_limit() {
+        let mut queue = RetryQueue::new();
+        assert_eq!(queue.schedule("task", MAX_ATTEMPTS - 1), Ok(()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn next_ready_pops_soonest_backoff_first_regardless_of_schedule_order() {
+        let mut queue = RetryQueue::new();
+        // Schedule the longer backoff first so pop order can only be
+        // explained by `Entry`'s reversed `Ord`, not insertion order.
+        queue.schedule("slow", 3).unwrap();
+        queue.schedule("fast", 0).unwrap();
+
+        let (task, attempts) = queue.next_ready().await.unwrap();
+        assert_eq!(task, "fast");
+        assert_eq!(attempts, 0);
+
+        let (task, attempts) = queue.next_ready().await.unwrap();
+        assert_eq!(task, "slow");
+        assert_eq!(attempts, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn next_ready_returns_none_once_drained() {
+        let mut queue = RetryQueue::new();
+        queue.schedule("only", 0).unwrap();
+        assert!(queue.next_ready().await.is_some());
+        assert!(queue.next_ready().await.is_none());
+    }
+}
@@ -0,0 +1,243 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Mutex,
+};
+use std::time::{Duration, Instant};
+
+use solana_client::{
+    client_error::Result as ClientResult,
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcBlockConfig, RpcTransactionConfig},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, UiConfirmedBlock,
+};
+use tracing::warn;
+use url::Url;
+
+/// Consecutive failures after which an endpoint is pulled out of rotation.
+const FAILURE_THRESHOLD: u32 = 5;
+/// How long a tripped endpoint sits out before being re-admitted.
+const COOLDOWN: Duration = Duration::from_secs(30);
+/// Weight given to a fresh latency sample vs the running average, so the
+/// score reacts to a degrading endpoint without being noisy.
+const LATENCY_EMA_ALPHA: f64 = 0.2;
+/// Score penalty per consecutive failure, so a flaky-but-fast endpoint still
+/// ranks behind a slower-but-reliable one.
+const FAILURE_PENALTY_MILLIS: f64 = 250.0;
+
+/// A single RPC endpoint, tracked by `RpcPool` for health and recency of
+/// failures.
+struct Endpoint {
+    client: RpcClient,
+    url: Url,
+    consecutive_failures: AtomicU32,
+    tripped_until: Mutex<Option<Instant>>,
+    latency_ema_millis: Mutex<f64>,
+}
+
+impl Endpoint {
+    fn new(url: Url, commitment: CommitmentConfig) -> Self {
+        let client = RpcClient::new_with_commitment(url.to_string(), commitment);
+        Self {
+            client,
+            url,
+            consecutive_failures: AtomicU32::new(0),
+            tripped_until: Mutex::new(None),
+            latency_ema_millis: Mutex::new(0.0),
+        }
+    }
+
+    fn is_available(&self) -> bool {
+        match *self.tripped_until.lock().unwrap() {
+            Some(until) => Instant::now() >= until,
+            None => true,
+        }
+    }
+
+    /// Lower is healthier: recent latency plus a penalty per consecutive failure.
+    fn score(&self) -> f64 {
+        let latency = *self.latency_ema_millis.lock().unwrap();
+        let failures = self.consecutive_failures.load(Ordering::Relaxed) as f64;
+        latency + failures * FAILURE_PENALTY_MILLIS
+    }
+
+    fn record_success(&self, elapsed: Duration) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        *self.tripped_until.lock().unwrap() = None;
+
+        let sample = elapsed.as_secs_f64() * 1000.0;
+        let mut ema = self.latency_ema_millis.lock().unwrap();
+        *ema = if *ema == 0.0 {
+            sample
+        } else {
+            LATENCY_EMA_ALPHA * sample + (1.0 - LATENCY_EMA_ALPHA) * *ema
+        };
+    }
+
+    fn record_failure(&self) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= FAILURE_THRESHOLD {
+            warn!(
+                "Endpoint {} tripped circuit breaker after {} consecutive failures",
+                self.url, failures
+            );
+            *self.tripped_until.lock().unwrap() = Some(Instant::now() + COOLDOWN);
+        }
+    }
+}
+
+/// A pool of RPC endpoints that routes each call to the healthiest one,
+/// scored by a moving average of recent latency and consecutive failures,
+/// and fails over to the next-healthiest endpoint on error. An endpoint is
+/// tripped out of rotation after too many consecutive failures and
+/// re-admitted once its cooldown elapses.
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+}
+
+impl RpcPool {
+    pub fn new(urls: &[Url], commitment: CommitmentConfig) -> Self {
+        assert!(!urls.is_empty(), "RpcPool requires at least one endpoint");
+        Self {
+            endpoints: urls
+                .iter()
+                .map(|url| Endpoint::new(url.clone(), commitment))
+                .collect(),
+        }
+    }
+
+    /// Orders endpoints from healthiest to least healthy, skipping those
+    /// still tripped by the circuit breaker. Falls back to every endpoint,
+    /// tripped or not, if all of them are currently tripped, so ingestion
+    /// doesn't stall entirely through a shared cooldown window.
+    fn ranked(&self) -> Vec<&Endpoint> {
+        let mut ranked: Vec<&Endpoint> = self.endpoints.iter().filter(|e| e.is_available()).collect();
+        if ranked.is_empty() {
+            ranked = self.endpoints.iter().collect();
+        }
+        ranked.sort_by(|a, b| a.score().partial_cmp(&b.score()).unwrap_or(std::cmp::Ordering::Equal));
+        ranked
+    }
+
+    pub async fn get_block_with_config(
+        &self,
+        slot: u64,
+        config: RpcBlockConfig,
+    ) -> ClientResult<UiConfirmedBlock> {
+        let mut last_err = None;
+        for endpoint in self.ranked() {
+            let started = Instant::now();
+            match endpoint.client.get_block_with_config(slot, config).await {
+                Ok(block) => {
+                    endpoint.record_success(started.elapsed());
+                    return Ok(block);
+                }
+                Err(e) => {
+                    endpoint.record_failure();
+                    warn!("get_block_with_config via {} failed: {}", endpoint.url, e);
+                    last_err = Some(e);
+                }
+            }
+        }
+        Err(last_err.expect("RpcPool::ranked never returns empty"))
+    }
+
+    pub async fn get_transaction_with_config(
+        &self,
+        signature: &Signature,
+        config: RpcTransactionConfig,
+    ) -> ClientResult<EncodedConfirmedTransactionWithStatusMeta> {
+        let mut last_err = None;
+        for endpoint in self.ranked() {
+            let started = Instant::now();
+            match endpoint
+                .client
+                .get_transaction_with_config(signature, config)
+                .await
+            {
+                Ok(transaction) => {
+                    endpoint.record_success(started.elapsed());
+                    return Ok(transaction);
+                }
+                Err(e) => {
+                    endpoint.record_failure();
+                    warn!(
+                        "get_transaction_with_config via {} failed: {}",
+                        endpoint.url, e
+                    );
+                    last_err = Some(e);
+                }
+            }
+        }
+        Err(last_err.expect("RpcPool::ranked never returns empty"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(n: usize) -> RpcPool {
+        let urls: Vec<Url> = (0..n)
+            .map(|i| Url::parse(&format!("http://127.0.0.1:{}", 8000 + i)).unwrap())
+            .collect();
+        RpcPool::new(&urls, CommitmentConfig::confirmed())
+    }
+
+    #[test]
+    fn ranked_orders_healthiest_endpoint_first() {
+        let pool = pool(2);
+        pool.endpoints[0].record_success(Duration::from_millis(10));
+        pool.endpoints[1].record_success(Duration::from_millis(200));
+
+        let ranked = pool.ranked();
+        assert_eq!(ranked[0].url, pool.endpoints[0].url);
+        assert_eq!(ranked[1].url, pool.endpoints[1].url);
+    }
+
+    #[test]
+    fn ranked_skips_an_endpoint_only_once_it_trips() {
+        let pool = pool(2);
+
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            pool.endpoints[0].record_failure();
+        }
+        assert!(pool.endpoints[0].is_available());
+        assert_eq!(pool.ranked().len(), 2);
+
+        pool.endpoints[0].record_failure();
+        assert!(!pool.endpoints[0].is_available());
+
+        let ranked = pool.ranked();
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].url, pool.endpoints[1].url);
+    }
+
+    #[test]
+    fn ranked_falls_back_to_every_endpoint_when_all_are_tripped() {
+        let pool = pool(2);
+        for endpoint in &pool.endpoints {
+            for _ in 0..FAILURE_THRESHOLD {
+                endpoint.record_failure();
+            }
+        }
+
+        assert!(pool.endpoints.iter().all(|e| !e.is_available()));
+        assert_eq!(pool.ranked().len(), 2);
+    }
+
+    #[test]
+    fn record_success_resets_failures_and_clears_trip() {
+        let pool = pool(1);
+        for _ in 0..FAILURE_THRESHOLD {
+            pool.endpoints[0].record_failure();
+        }
+        assert!(!pool.endpoints[0].is_available());
+
+        pool.endpoints[0].record_success(Duration::from_millis(5));
+        assert!(pool.endpoints[0].is_available());
+        assert_eq!(pool.endpoints[0].consecutive_failures.load(Ordering::Relaxed), 0);
+    }
+}
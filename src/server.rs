@@ -1,4 +1,4 @@
-use std::io::Error;
+use std::{io::Error, sync::Arc};
 
 use axum::{
     extract::{Query, State},
@@ -8,27 +8,34 @@ use axum::{
     Json, Router,
 };
 use chrono::NaiveDate;
-use serde::{Deserialize, Serialize};
-use surrealdb::{Connection, Surreal};
+use serde::Deserialize;
 use tokio::{
     net::TcpListener,
     sync::broadcast::{self},
 };
 
-#[derive(Debug, Clone)]
-struct ServerState<C: Connection + Clone> {
-    db: Surreal<C>,
+use crate::{
+    metrics::{ErrorKind, Metrics},
+    store::{ConfirmationStatus, TransactionStore},
+};
+
+#[derive(Clone)]
+struct ServerState {
+    store: Arc<dyn TransactionStore>,
+    metrics: Arc<Metrics>,
 }
 
-pub async fn run<C: Connection + Clone>(
+pub async fn run(
     listener: TcpListener,
-    db: Surreal<C>,
+    store: Arc<dyn TransactionStore>,
+    metrics: Arc<Metrics>,
     mut shutdown: broadcast::Receiver<()>,
 ) -> Result<(), Error> {
-    let state = ServerState { db };
+    let state = ServerState { store, metrics };
 
     let app = Router::new()
         .route("/transactions", get(handle))
+        .route("/metrics", get(handle_metrics))
         .with_state(state);
 
     axum::serve(listener, app)
@@ -45,30 +52,26 @@ pub async fn run<C: Connection + Clone>(
 struct Q {
     id: Option<String>,
     day: Option<NaiveDate>,
+    confirmation_status: Option<ConfirmationStatus>,
 }
-async fn handle<C: Connection + Clone>(
+async fn handle(
     Query(q): Query<Q>,
-    State(state): State<ServerState<C>>,
+    State(state): State<ServerState>,
 ) -> Result<impl IntoResponse, StatusCode> {
-    #[derive(Debug, Deserialize, Serialize)]
-    struct Transaction {
-        signature: String,
-        slot: u64,
-        block_hash: String,
-        timestamp: i64,
-    }
-
     // Query by signature if provided
     if let Some(id) = q.id {
-        let mut result = state
-            .db
-            .query("SELECT signature, slot, block_hash, timestamp FROM type::table($table) WHERE signature = type::string($signature)")
-            .bind(("table", "transactions"))
-            .bind(("signature", id))
-            .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-        let transaction: Vec<Transaction> = result.take(0).unwrap();
+        let query_timer = state.metrics.db_query_latency.start_timer();
+        let result = state
+            .store
+            .get_by_signature(&id, q.confirmation_status)
+            .await;
+        query_timer.observe_duration();
+
+        let transaction = result.map_err(|_| {
+            state.metrics.record_error(ErrorKind::DbQuery);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
         return Ok(Json(transaction));
     }
 
@@ -77,18 +80,25 @@ async fn handle<C: Connection + Clone>(
         let start_timestamp = day.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
         let end_timestamp = day.and_hms_opt(23, 59, 59).unwrap().and_utc().timestamp();
 
-        let mut result = state
-            .db
-            .query("SELECT signature, slot, block_hash, timestamp FROM type::table($table) WHERE timestamp >= $start AND timestamp <= $end")
-            .bind(("table", "transactions"))
-            .bind(("start", start_timestamp))
-            .bind(("end", end_timestamp))
-            .await
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let query_timer = state.metrics.db_query_latency.start_timer();
+        let result = state
+            .store
+            .get_by_day_range(start_timestamp, end_timestamp, q.confirmation_status)
+            .await;
+        query_timer.observe_duration();
+
+        let transactions = result.map_err(|_| {
+            state.metrics.record_error(ErrorKind::DbQuery);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-        let transactions: Vec<Transaction> = result.take(0).unwrap();
         return Ok(Json(transactions));
     }
 
     Err(StatusCode::NOT_FOUND)
 }
+
+/// Renders the process's Prometheus metrics for scraping.
+async fn handle_metrics(State(state): State<ServerState>) -> impl IntoResponse {
+    state.metrics.render()
+}
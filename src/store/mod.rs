@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use solana_transaction_status_client_types::EncodedConfirmedTransactionWithStatusMeta;
+use surrealdb::{engine::remote::ws::Ws, opt::auth::Root, Surreal};
+use thiserror::Error;
+use tracing::info;
+
+pub mod postgres;
+pub mod surreal;
+
+/// The backend a deployment stores transactions in, selected via `--db-backend`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DbBackend {
+    Surreal,
+    Postgres,
+}
+
+#[derive(Debug, Error)]
+pub enum DbConnectError {
+    #[error(transparent)]
+    Surrealdb(#[from] surrealdb::Error),
+
+    #[error(transparent)]
+    Store(#[from] StoreError),
+
+    #[error("--{0} is required when --db-backend={1:?}")]
+    MissingDbConfig(&'static str, DbBackend),
+}
+
+/// CLI arguments selecting and configuring a transaction store backend,
+/// shared by every binary that needs one.
+#[derive(Debug, Parser)]
+pub struct DbArgs {
+    #[arg(long, env, value_enum, default_value_t = DbBackend::Surreal)]
+    pub db_backend: DbBackend,
+
+    #[arg(long, short = 'a', env)]
+    pub db_addr: Option<String>,
+
+    #[arg(long, short = 'u', env)]
+    pub db_user: Option<String>,
+
+    #[arg(long, short = 'p', env)]
+    pub db_pass: Option<String>,
+
+    #[arg(long, env)]
+    pub db_ns: Option<String>,
+
+    #[arg(long, env)]
+    pub db_db: Option<String>,
+
+    /// Postgres connection string, required when `--db-backend postgres` is used.
+    #[arg(long, env)]
+    pub postgres_url: Option<String>,
+}
+
+impl DbArgs {
+    /// Connects to the backend selected by `--db-backend`, validating that
+    /// the fields it needs were supplied.
+    pub async fn connect(self) -> Result<Arc<dyn TransactionStore>, DbConnectError> {
+        match self.db_backend {
+            DbBackend::Surreal => {
+                let db_addr = self
+                    .db_addr
+                    .ok_or(DbConnectError::MissingDbConfig("db-addr", self.db_backend))?;
+                let db_user = self
+                    .db_user
+                    .ok_or(DbConnectError::MissingDbConfig("db-user", self.db_backend))?;
+                let db_pass = self
+                    .db_pass
+                    .ok_or(DbConnectError::MissingDbConfig("db-pass", self.db_backend))?;
+                let db_ns = self
+                    .db_ns
+                    .ok_or(DbConnectError::MissingDbConfig("db-ns", self.db_backend))?;
+                let db_db = self
+                    .db_db
+                    .ok_or(DbConnectError::MissingDbConfig("db-db", self.db_backend))?;
+
+                info!("Connecting to SurrealDB at {}", db_addr);
+                let db = Surreal::new::<Ws>(&db_addr).await?;
+                db.signin(Root {
+                    username: &db_user,
+                    password: &db_pass,
+                })
+                .await?;
+                db.use_ns(&db_ns).use_db(&db_db).await?;
+
+                Ok(Arc::new(surreal::SurrealStore::new(db)))
+            }
+            DbBackend::Postgres => {
+                let postgres_url = self
+                    .postgres_url
+                    .ok_or(DbConnectError::MissingDbConfig("postgres-url", self.db_backend))?;
+
+                info!("Connecting to Postgres at {}", postgres_url);
+                Ok(Arc::new(postgres::PostgresStore::connect(&postgres_url).await?))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error(transparent)]
+    Surrealdb(#[from] surrealdb::Error),
+
+    #[error(transparent)]
+    Postgres(#[from] tokio_postgres::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Where a transaction stands in Solana's confirmed/finalized lifecycle, as
+/// tracked by the fetcher's dual-track ingestion mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmationStatus {
+    /// Observed at `confirmed` commitment; may still be dropped on a fork.
+    Confirmed,
+    /// Observed again at `finalized` commitment; will not be rolled back.
+    Finalized,
+    /// Was `confirmed` but never finalized, because its slot was dropped on a fork.
+    Orphaned,
+}
+
+impl ConfirmationStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConfirmationStatus::Confirmed => "confirmed",
+            ConfirmationStatus::Finalized => "finalized",
+            ConfirmationStatus::Orphaned => "orphaned",
+        }
+    }
+}
+
+impl std::str::FromStr for ConfirmationStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "confirmed" => Ok(Self::Confirmed),
+            "finalized" => Ok(Self::Finalized),
+            "orphaned" => Ok(Self::Orphaned),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A transaction as ingested from the chain, ready to be persisted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_hash: String,
+    pub timestamp: i64,
+    pub confirmation_status: ConfirmationStatus,
+    pub data: EncodedConfirmedTransactionWithStatusMeta,
+}
+
+/// The subset of a stored transaction returned by lookup queries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_hash: String,
+    pub timestamp: i64,
+    pub confirmation_status: ConfirmationStatus,
+}
+
+/// Persists and looks up ingested transactions, independent of the backing
+/// database. Implemented by [`surreal::SurrealStore`] and
+/// [`postgres::PostgresStore`] so ingestion and serving logic never embeds a
+/// particular database's query language.
+#[async_trait]
+pub trait TransactionStore: Send + Sync {
+    /// The highest slot already persisted, used to resume ingestion after a restart.
+    async fn latest_slot(&self) -> Option<u64>;
+
+    async fn store_transaction(&self, transaction: Transaction) -> Result<(), StoreError>;
+
+    /// Stores every transaction in a single round-trip, returning how many were written.
+    async fn store_batch(&self, transactions: &[Transaction]) -> Result<usize, StoreError>;
+
+    /// Inserts `transaction`, or updates the existing row for its signature if
+    /// one already exists. Used by the finalized leg of dual-track ingestion
+    /// to upgrade a previously `confirmed` row in place.
+    async fn upsert_transaction(&self, transaction: Transaction) -> Result<(), StoreError>;
+
+    /// Flips every still-`confirmed` row at `slot` to `orphaned`, for slots
+    /// that were confirmed but dropped on a fork before finalizing.
+    async fn mark_orphaned(&self, slot: u64) -> Result<(), StoreError>;
+
+    async fn get_by_signature(
+        &self,
+        signature: &str,
+        confirmation_status: Option<ConfirmationStatus>,
+    ) -> Result<Vec<StoredTransaction>, StoreError>;
+
+    async fn get_by_day_range(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        confirmation_status: Option<ConfirmationStatus>,
+    ) -> Result<Vec<StoredTransaction>, StoreError>;
+}
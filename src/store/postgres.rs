@@ -0,0 +1,237 @@
+use async_trait::async_trait;
+use tokio_postgres::{Client, NoTls, Row};
+use tracing::{error, info};
+
+use super::{ConfirmationStatus, StoreError, StoredTransaction, Transaction, TransactionStore};
+
+/// Wraps a `tokio-postgres` client, storing each transaction's decoded
+/// payload as a `jsonb` column alongside its scalar fields; only `signature`
+/// (the primary key) is indexed.
+pub struct PostgresStore {
+    client: Client,
+}
+
+impl PostgresStore {
+    pub async fn connect(conn_str: &str) -> Result<Self, StoreError> {
+        let (client, connection) = tokio_postgres::connect(conn_str, NoTls).await?;
+
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                error!("Postgres connection closed with error: {}", e);
+            }
+        });
+
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS transactions (
+                    signature TEXT PRIMARY KEY,
+                    slot BIGINT NOT NULL,
+                    block_hash TEXT NOT NULL,
+                    timestamp BIGINT NOT NULL,
+                    confirmation_status TEXT NOT NULL DEFAULT 'confirmed',
+                    data JSONB NOT NULL
+                )",
+            )
+            .await?;
+
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl TransactionStore for PostgresStore {
+    async fn latest_slot(&self) -> Option<u64> {
+        match self
+            .client
+            .query_opt(
+                "SELECT slot FROM transactions ORDER BY slot DESC LIMIT 1",
+                &[],
+            )
+            .await
+        {
+            Ok(Some(row)) => {
+                let slot: i64 = row.get(0);
+                info!("Recovered latest slot from database: {}", slot);
+                Some(slot as u64)
+            }
+            Ok(None) => {
+                tracing::warn!("No slot data found in database, starting from next received slot.");
+                None
+            }
+            Err(e) => {
+                error!("Failed to fetch latest slot from database: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn store_transaction(&self, transaction: Transaction) -> Result<(), StoreError> {
+        let data = serde_json::to_value(&transaction.data)?;
+        self.client
+            .execute(
+                "INSERT INTO transactions (signature, slot, block_hash, timestamp, confirmation_status, data)
+                 VALUES ($1, $2, $3, $4, $5, $6)
+                 ON CONFLICT (signature) DO NOTHING",
+                &[
+                    &transaction.signature,
+                    &(transaction.slot as i64),
+                    &transaction.block_hash,
+                    &transaction.timestamp,
+                    &transaction.confirmation_status.as_str(),
+                    &data,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Bulk-inserts via a single `INSERT ... SELECT * FROM UNNEST(...)`
+    /// statement, so a whole block's worth of transactions is written in one
+    /// round trip instead of one `INSERT` per transaction.
+    async fn store_batch(&self, transactions: &[Transaction]) -> Result<usize, StoreError> {
+        if transactions.is_empty() {
+            return Ok(0);
+        }
+
+        let mut signatures = Vec::with_capacity(transactions.len());
+        let mut slots = Vec::with_capacity(transactions.len());
+        let mut block_hashes = Vec::with_capacity(transactions.len());
+        let mut timestamps = Vec::with_capacity(transactions.len());
+        let mut statuses = Vec::with_capacity(transactions.len());
+        let mut datas = Vec::with_capacity(transactions.len());
+
+        for transaction in transactions {
+            signatures.push(transaction.signature.clone());
+            slots.push(transaction.slot as i64);
+            block_hashes.push(transaction.block_hash.clone());
+            timestamps.push(transaction.timestamp);
+            statuses.push(transaction.confirmation_status.as_str());
+            datas.push(serde_json::to_value(&transaction.data)?);
+        }
+
+        let rows_affected = self
+            .client
+            .execute(
+                "INSERT INTO transactions (signature, slot, block_hash, timestamp, confirmation_status, data)
+                 SELECT * FROM UNNEST($1::text[], $2::bigint[], $3::text[], $4::bigint[], $5::text[], $6::jsonb[])
+                 ON CONFLICT (signature) DO NOTHING",
+                &[&signatures, &slots, &block_hashes, &timestamps, &statuses, &datas],
+            )
+            .await?;
+
+        Ok(rows_affected as usize)
+    }
+
+    async fn upsert_transaction(&self, transaction: Transaction) -> Result<(), StoreError> {
+        let data = serde_json::to_value(&transaction.data)?;
+        self.client
+            .execute(
+                "INSERT INTO transactions (signature, slot, block_hash, timestamp, confirmation_status, data)
+                 VALUES ($1, $2, $3, $4, $5, $6)
+                 ON CONFLICT (signature) DO UPDATE SET
+                     slot = EXCLUDED.slot,
+                     block_hash = EXCLUDED.block_hash,
+                     timestamp = EXCLUDED.timestamp,
+                     confirmation_status = EXCLUDED.confirmation_status,
+                     data = EXCLUDED.data",
+                &[
+                    &transaction.signature,
+                    &(transaction.slot as i64),
+                    &transaction.block_hash,
+                    &transaction.timestamp,
+                    &transaction.confirmation_status.as_str(),
+                    &data,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn mark_orphaned(&self, slot: u64) -> Result<(), StoreError> {
+        self.client
+            .execute(
+                "UPDATE transactions SET confirmation_status = $1 WHERE slot = $2 AND confirmation_status = $3",
+                &[
+                    &ConfirmationStatus::Orphaned.as_str(),
+                    &(slot as i64),
+                    &ConfirmationStatus::Confirmed.as_str(),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_by_signature(
+        &self,
+        signature: &str,
+        confirmation_status: Option<ConfirmationStatus>,
+    ) -> Result<Vec<StoredTransaction>, StoreError> {
+        let rows = match confirmation_status {
+            Some(status) => {
+                self.client
+                    .query(
+                        "SELECT signature, slot, block_hash, timestamp, confirmation_status \
+                         FROM transactions WHERE signature = $1 AND confirmation_status = $2",
+                        &[&signature, &status.as_str()],
+                    )
+                    .await?
+            }
+            None => {
+                self.client
+                    .query(
+                        "SELECT signature, slot, block_hash, timestamp, confirmation_status \
+                         FROM transactions WHERE signature = $1",
+                        &[&signature],
+                    )
+                    .await?
+            }
+        };
+
+        Ok(rows.iter().map(row_to_stored_transaction).collect())
+    }
+
+    async fn get_by_day_range(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        confirmation_status: Option<ConfirmationStatus>,
+    ) -> Result<Vec<StoredTransaction>, StoreError> {
+        let rows = match confirmation_status {
+            Some(status) => {
+                self.client
+                    .query(
+                        "SELECT signature, slot, block_hash, timestamp, confirmation_status \
+                         FROM transactions \
+                         WHERE timestamp >= $1 AND timestamp <= $2 AND confirmation_status = $3",
+                        &[&start_timestamp, &end_timestamp, &status.as_str()],
+                    )
+                    .await?
+            }
+            None => {
+                self.client
+                    .query(
+                        "SELECT signature, slot, block_hash, timestamp, confirmation_status \
+                         FROM transactions WHERE timestamp >= $1 AND timestamp <= $2",
+                        &[&start_timestamp, &end_timestamp],
+                    )
+                    .await?
+            }
+        };
+
+        Ok(rows.iter().map(row_to_stored_transaction).collect())
+    }
+}
+
+fn row_to_stored_transaction(row: &Row) -> StoredTransaction {
+    let slot: i64 = row.get("slot");
+    let confirmation_status: String = row.get("confirmation_status");
+    StoredTransaction {
+        signature: row.get("signature"),
+        slot: slot as u64,
+        block_hash: row.get("block_hash"),
+        timestamp: row.get("timestamp"),
+        confirmation_status: confirmation_status
+            .parse()
+            .unwrap_or(ConfirmationStatus::Confirmed),
+    }
+}
@@ -0,0 +1,186 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use surrealdb::{Connection, RecordId, Surreal};
+use tracing::{error, info, warn};
+
+use super::{ConfirmationStatus, StoreError, StoredTransaction, Transaction, TransactionStore};
+
+/// Wraps a `Surreal` connection, keeping the raw SurrealQL this crate
+/// originally spoke contained to a single place.
+pub struct SurrealStore<C: Connection> {
+    db: Surreal<C>,
+}
+
+impl<C: Connection> SurrealStore<C> {
+    pub fn new(db: Surreal<C>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl<C: Connection> TransactionStore for SurrealStore<C> {
+    async fn latest_slot(&self) -> Option<u64> {
+        #[derive(Debug, Deserialize)]
+        struct SlotResult {
+            slot: u64,
+        }
+
+        match self
+            .db
+            .query("SELECT slot FROM transactions ORDER BY slot DESC LIMIT 1")
+            .await
+        {
+            Ok(mut res) => {
+                if let Some(slot) = res.take::<Option<SlotResult>>(0).ok().flatten() {
+                    info!("Recovered latest slot from database: {}", slot.slot);
+                    Some(slot.slot)
+                } else {
+                    warn!("No slot data found in database, starting from next received slot.");
+                    None
+                }
+            }
+            Err(e) => {
+                error!("Failed to fetch latest slot from database: {}", e);
+                None
+            }
+        }
+    }
+
+    async fn store_transaction(&self, transaction: Transaction) -> Result<(), StoreError> {
+        #[derive(Debug, Deserialize)]
+        struct Id {
+            #[allow(dead_code)]
+            id: RecordId,
+        }
+
+        self.db
+            .create::<Option<Id>>("transactions")
+            .content(transaction)
+            .await?;
+        Ok(())
+    }
+
+    async fn store_batch(&self, transactions: &[Transaction]) -> Result<usize, StoreError> {
+        #[derive(Debug, Deserialize)]
+        struct Id {
+            #[allow(dead_code)]
+            id: RecordId,
+        }
+
+        let ids = self
+            .db
+            .insert::<Vec<Id>>("transactions")
+            .content(transactions)
+            .await?;
+        Ok(ids.len())
+    }
+
+    async fn upsert_transaction(&self, transaction: Transaction) -> Result<(), StoreError> {
+        #[derive(Debug, Deserialize)]
+        struct Id {
+            id: RecordId,
+        }
+
+        let mut existing = self
+            .db
+            .query("SELECT id FROM transactions WHERE signature = $signature LIMIT 1")
+            .bind(("signature", transaction.signature.clone()))
+            .await?;
+
+        match existing.take::<Option<Id>>(0)? {
+            Some(id) => {
+                self.db
+                    .query(
+                        "UPDATE $id SET slot = $slot, block_hash = $block_hash, \
+                         timestamp = $timestamp, data = $data, confirmation_status = $status",
+                    )
+                    .bind(("id", id.id))
+                    .bind(("slot", transaction.slot))
+                    .bind(("block_hash", transaction.block_hash))
+                    .bind(("timestamp", transaction.timestamp))
+                    .bind(("data", transaction.data))
+                    .bind(("status", transaction.confirmation_status))
+                    .await?;
+            }
+            None => {
+                self.db
+                    .create::<Option<Id>>("transactions")
+                    .content(transaction)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn mark_orphaned(&self, slot: u64) -> Result<(), StoreError> {
+        self.db
+            .query(
+                "UPDATE transactions SET confirmation_status = $orphaned \
+                 WHERE slot = $slot AND confirmation_status = $confirmed",
+            )
+            .bind(("slot", slot))
+            .bind(("orphaned", ConfirmationStatus::Orphaned))
+            .bind(("confirmed", ConfirmationStatus::Confirmed))
+            .await?;
+        Ok(())
+    }
+
+    async fn get_by_signature(
+        &self,
+        signature: &str,
+        confirmation_status: Option<ConfirmationStatus>,
+    ) -> Result<Vec<StoredTransaction>, StoreError> {
+        let query = match confirmation_status {
+            Some(_) => {
+                "SELECT signature, slot, block_hash, timestamp, confirmation_status \
+                 FROM type::table($table) \
+                 WHERE signature = type::string($signature) AND confirmation_status = $status"
+            }
+            None => {
+                "SELECT signature, slot, block_hash, timestamp, confirmation_status \
+                 FROM type::table($table) WHERE signature = type::string($signature)"
+            }
+        };
+
+        let mut result = self
+            .db
+            .query(query)
+            .bind(("table", "transactions"))
+            .bind(("signature", signature.to_string()))
+            .bind(("status", confirmation_status))
+            .await?;
+
+        Ok(result.take(0)?)
+    }
+
+    async fn get_by_day_range(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        confirmation_status: Option<ConfirmationStatus>,
+    ) -> Result<Vec<StoredTransaction>, StoreError> {
+        let query = match confirmation_status {
+            Some(_) => {
+                "SELECT signature, slot, block_hash, timestamp, confirmation_status \
+                 FROM type::table($table) \
+                 WHERE timestamp >= $start AND timestamp <= $end AND confirmation_status = $status"
+            }
+            None => {
+                "SELECT signature, slot, block_hash, timestamp, confirmation_status \
+                 FROM type::table($table) WHERE timestamp >= $start AND timestamp <= $end"
+            }
+        };
+
+        let mut result = self
+            .db
+            .query(query)
+            .bind(("table", "transactions"))
+            .bind(("start", start_timestamp))
+            .bind(("end", end_timestamp))
+            .bind(("status", confirmation_status))
+            .await?;
+
+        Ok(result.take(0)?)
+    }
+}